@@ -0,0 +1,163 @@
+//! Derive macro for `endianrw`'s `ReadFrom`/`WriteTo` traits.
+//!
+//! `#[derive(EndianIO)]` generates field-by-field implementations that call
+//! `read_as`/`write_as` in declaration order, defaulting to the impl's `B`
+//! byte order while honoring a per-field `#[endian(BigEndian)]` /
+//! `#[endian(LittleEndian)]` override. A field whose type itself derives
+//! `EndianIO` (or otherwise implements `ReadFrom`/`WriteTo`) is marked
+//! `#[endian(nested)]`, which dispatches to that trait instead of
+//! `read_as`/`write_as`. See `endianrw::codec` for the traits this
+//! implements.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use std::collections::HashSet;
+
+use proc_macro::TokenStream;
+use syn::{Data, DeriveInput, Fields};
+
+#[proc_macro_derive(EndianIO, attributes(endian))]
+pub fn derive_endian_io(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).expect("EndianIO: failed to parse input");
+    let name = &input.ident;
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => panic!("EndianIO only supports structs with named fields"),
+        },
+        _ => panic!("EndianIO only supports structs"),
+    };
+
+    let reads = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        match field_kind(field) {
+            FieldKind::Nested => quote! {
+                #ident: <#ty as ::endianrw::codec::ReadFrom<B>>::read_from(r)?,
+            },
+            FieldKind::Primitive(order) => {
+                let order = order_tokens(order);
+                quote! {
+                    #ident: ::endianrw::EndianReadExt::read_as::<#order, #ty>(r)?,
+                }
+            }
+        }
+    });
+
+    let writes = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        match field_kind(field) {
+            // Pass nested fields by reference: unlike the `ByteTransform`
+            // primitives, a nested `EndianIO` type isn't necessarily `Copy`.
+            FieldKind::Nested => quote! {
+                ::endianrw::codec::WriteTo::<B>::write_to(&self.#ident, w)?;
+            },
+            FieldKind::Primitive(order) => {
+                let order = order_tokens(order);
+                quote! {
+                    ::endianrw::EndianWriteExt::write_as::<#order, _>(w, self.#ident)?;
+                }
+            }
+        }
+    });
+
+    // Any field without an `#[endian(...)]` override is read/written using
+    // the impl's own `B`: a default-order primitive field needs a
+    // `ByteTransform<Ty>` bound on `B`, and a nested field needs its type
+    // to implement `ReadFrom<B>`/`WriteTo<B>` — or the generated impl
+    // won't compile.
+    let mut read_seen = HashSet::new();
+    let mut write_seen = HashSet::new();
+    let mut read_bounds = Vec::new();
+    let mut write_bounds = Vec::new();
+
+    for field in fields.iter() {
+        let ty = &field.ty;
+        let key = quote! { #ty }.to_string();
+        match field_kind(field) {
+            FieldKind::Primitive(None) => {
+                if read_seen.insert(key.clone()) {
+                    read_bounds.push(quote! { B: ::endianrw::ByteTransform<#ty> });
+                }
+                if write_seen.insert(key) {
+                    write_bounds.push(quote! { B: ::endianrw::ByteTransform<#ty> });
+                }
+            }
+            FieldKind::Primitive(Some(_)) => {}
+            FieldKind::Nested => {
+                if read_seen.insert(key.clone()) {
+                    read_bounds.push(quote! { #ty: ::endianrw::codec::ReadFrom<B> });
+                }
+                if write_seen.insert(key) {
+                    write_bounds.push(quote! { #ty: ::endianrw::codec::WriteTo<B> });
+                }
+            }
+        }
+    }
+
+    let read_bound = if read_bounds.is_empty() {
+        quote! {}
+    } else {
+        quote! { where #(#read_bounds),* }
+    };
+    let write_bound = if write_bounds.is_empty() {
+        quote! {}
+    } else {
+        quote! { where #(#write_bounds),* }
+    };
+
+    let expanded = quote! {
+        impl<B> ::endianrw::codec::ReadFrom<B> for #name #read_bound {
+            fn read_from<R: ::std::io::Read>(r: &mut R) -> ::std::io::Result<Self> {
+                Ok(#name {
+                    #(#reads)*
+                })
+            }
+        }
+
+        impl<B> ::endianrw::codec::WriteTo<B> for #name #write_bound {
+            fn write_to<W: ::std::io::Write>(&self, w: &mut W) -> ::std::io::Result<()> {
+                #(#writes)*
+                Ok(())
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Whether a field is a `ByteTransform` primitive (with an optional
+/// `#[endian(...)]` order override) or a nested `#[endian(nested)]` type.
+enum FieldKind {
+    Primitive(Option<syn::Ident>),
+    Nested,
+}
+
+fn field_kind(field: &syn::Field) -> FieldKind {
+    for attr in &field.attrs {
+        if attr.path.is_ident("endian") {
+            let order: syn::Ident = attr
+                .parse_args()
+                .expect("endian attribute expects a byte order identifier or `nested`");
+            if order == "nested" {
+                return FieldKind::Nested;
+            }
+            return FieldKind::Primitive(Some(order));
+        }
+    }
+    FieldKind::Primitive(None)
+}
+
+/// The byte order a primitive field should use: its `#[endian(...)]`
+/// override if present, otherwise the impl's own `B` type parameter.
+fn order_tokens(order: Option<syn::Ident>) -> proc_macro2::TokenStream {
+    match order {
+        Some(order) => quote! { ::endianrw::#order },
+        None => quote! { B },
+    }
+}
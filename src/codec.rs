@@ -0,0 +1,21 @@
+//! Struct-level (de)serialization built on top of `EndianReadExt`/`EndianWriteExt`.
+//!
+//! `#[derive(EndianIO)]` (see the `endianrw_derive` crate) generates the
+//! `ReadFrom`/`WriteTo` impls described here, so a struct of primitive
+//! fields can round-trip without a hand-written `read_as`/`write_as` call
+//! per field. `B` is the byte order fields use by default; a field can
+//! opt out with `#[endian(BigEndian)]`/`#[endian(LittleEndian)]`.
+
+use std::io;
+
+/// Read `Self` field by field, in declaration order, using byte order `B`
+/// for any field that doesn't declare its own `#[endian(...)]` override.
+pub trait ReadFrom<B>: Sized {
+    fn read_from<R: io::Read>(r: &mut R) -> io::Result<Self>;
+}
+
+/// Write `Self` field by field, in declaration order, using byte order `B`
+/// for any field that doesn't declare its own `#[endian(...)]` override.
+pub trait WriteTo<B> {
+    fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()>;
+}
@@ -28,8 +28,22 @@
 //! assert_eq!(&[0x67, 0x45, 0x23, 0x01], &data[..]);
 //! ```
 
+use std::any::TypeId;
+use std::convert::TryFrom;
 use std::io;
-use std::mem::transmute;
+use std::mem::{size_of, transmute};
+use std::slice;
+
+pub mod codec;
+pub mod varint;
+
+#[cfg(feature = "derive")]
+extern crate endianrw_derive;
+
+/// `#[derive(EndianIO)]`, re-exported behind the `derive` feature. See
+/// [`codec`] for the `ReadFrom`/`WriteTo` traits it implements.
+#[cfg(feature = "derive")]
+pub use endianrw_derive::EndianIO;
 
 trait AsSlice<T>: AsRef<[T]> + AsMut<[T]> {}
 impl<T, V: AsRef<[T]> + AsMut<[T]>> AsSlice<T> for V {}
@@ -47,6 +61,12 @@ pub trait ByteTransform<T> {
 
     /// Create large enough buffer to store T
     fn buffer() -> Self::Buffer;
+
+    /// Fix up the byte order of a `T` that already sits in native memory
+    /// layout (raw bytes copied straight into place), without the
+    /// `Buffer` round-trip that `from_bytes`/`to_bytes` pay per element.
+    /// Used by the bulk slice transfer methods.
+    fn correct(val: T) -> T;
 }
 
 /// Big endian byte order
@@ -72,6 +92,8 @@ macro_rules! impl_bytetransform {
         impl_bytetransform!($byteorder, i16, 2, $convertfn);
         impl_bytetransform!($byteorder, i32, 4, $convertfn);
         impl_bytetransform!($byteorder, i64, 8, $convertfn);
+        impl_bytetransform!($byteorder, u128, 16, $convertfn);
+        impl_bytetransform!($byteorder, i128, 16, $convertfn);
         impl_bytetransform!($byteorder, f32, 4, $convertfn, u32);
         impl_bytetransform!($byteorder, f64, 8, $convertfn, u64);
     };
@@ -95,6 +117,11 @@ macro_rules! impl_bytetransform {
             fn buffer() -> Self::Buffer {
                 [0; $typesize]
             }
+
+            #[inline]
+            fn correct(val: $typename) -> $typename {
+                val.$convertfn()
+            }
         }
     };
 
@@ -117,6 +144,11 @@ macro_rules! impl_bytetransform {
             fn buffer() -> Self::Buffer {
                 [0; $typesize]
             }
+
+            #[inline]
+            fn correct(val: $typename) -> $typename {
+                unsafe { transmute(transmute::<_, $convertas>(val).$convertfn()) }
+            }
         }
 
     }
@@ -125,14 +157,161 @@ macro_rules! impl_bytetransform {
 impl_bytetransform!(LittleEndian, to_le);
 impl_bytetransform!(BigEndian, to_be);
 
+#[inline]
+fn read_from_slice<B: ByteTransform<T>, T>(buf: &[u8]) -> T {
+    let mut tmp = B::buffer();
+    let len = tmp.as_ref().len();
+    tmp.as_mut().copy_from_slice(&buf[..len]);
+    B::from_bytes(tmp)
+}
+
+#[inline]
+fn write_to_slice<B: ByteTransform<T>, T>(buf: &mut [u8], val: T) {
+    let tmp = B::to_bytes(val);
+    let src = tmp.as_ref();
+    buf[..src.len()].copy_from_slice(src);
+}
+
+impl BigEndian {
+    /// Read a `T` directly out of `buf`, without a `Read` adapter. Panics
+    /// if `buf` is shorter than `T`'s encoded size.
+    pub fn read<T>(buf: &[u8]) -> T where BigEndian: ByteTransform<T> {
+        read_from_slice::<BigEndian, T>(buf)
+    }
+
+    /// Write `val` directly into `buf`, without a `Write` adapter. Panics
+    /// if `buf` is shorter than `T`'s encoded size.
+    pub fn write<T>(buf: &mut [u8], val: T) where BigEndian: ByteTransform<T> {
+        write_to_slice::<BigEndian, T>(buf, val)
+    }
+}
+
+impl LittleEndian {
+    /// Read a `T` directly out of `buf`, without a `Read` adapter. Panics
+    /// if `buf` is shorter than `T`'s encoded size.
+    pub fn read<T>(buf: &[u8]) -> T where LittleEndian: ByteTransform<T> {
+        read_from_slice::<LittleEndian, T>(buf)
+    }
+
+    /// Write `val` directly into `buf`, without a `Write` adapter. Panics
+    /// if `buf` is shorter than `T`'s encoded size.
+    pub fn write<T>(buf: &mut [u8], val: T) where LittleEndian: ByteTransform<T> {
+        write_to_slice::<LittleEndian, T>(buf, val)
+    }
+}
+
+/// Assembles/disassembles a runtime-sized (1 to 8 byte) integer according to
+/// a byte order, for the `nbytes`-wide reads and writes that don't fit any
+/// of the fixed `u8..u64` widths (e.g. 24-bit or 40-bit fields).
+pub trait VariableWidth {
+    /// Assemble `buf` (length `1..=8`) into a `u64`.
+    fn assemble(buf: &[u8]) -> u64;
+
+    /// Disassemble `val` into `buf` (length `1..=8`).
+    fn disassemble(val: u64, buf: &mut [u8]);
+}
+
+impl VariableWidth for LittleEndian {
+    #[inline]
+    fn assemble(buf: &[u8]) -> u64 {
+        let mut result: u64 = 0;
+        for i in 0..buf.len() {
+            result |= (buf[i] as u64) << (8 * i);
+        }
+        result
+    }
+
+    #[inline]
+    fn disassemble(val: u64, buf: &mut [u8]) {
+        for i in 0..buf.len() {
+            buf[i] = (val >> (8 * i)) as u8;
+        }
+    }
+}
+
+impl VariableWidth for BigEndian {
+    #[inline]
+    fn assemble(buf: &[u8]) -> u64 {
+        let mut result: u64 = 0;
+        for i in 0..buf.len() {
+            result = (result << 8) | (buf[i] as u64);
+        }
+        result
+    }
+
+    #[inline]
+    fn disassemble(val: u64, buf: &mut [u8]) {
+        let len = buf.len();
+        for i in 0..len {
+            buf[i] = (val >> (8 * (len - 1 - i))) as u8;
+        }
+    }
+}
+
+/// Sign-extend the low `nbytes` bytes of `val` to a full `i64`.
+#[inline]
+fn extend_sign(val: u64, nbytes: usize) -> i64 {
+    let shift = 64 - 8 * nbytes;
+    ((val << shift) as i64) >> shift
+}
+
+fn check_nbytes(nbytes: usize) -> io::Result<()> {
+    if nbytes == 0 || nbytes > 8 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "nbytes must be in 1..=8"
+        ))
+    }
+    Ok(())
+}
+
 /// Extension trait that allows to read endian specified primitive types from it
 pub trait EndianReadExt {
     fn read_as<B: ByteTransform<T>, T>(&mut self) -> io::Result<T>;
+
+    /// Read an `nbytes`-wide (`1..=8`) unsigned integer.
+    fn read_uint<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<u64>;
+
+    /// Read an `nbytes`-wide (`1..=8`) signed integer, sign-extended to `i64`.
+    fn read_int<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<i64>;
+
+    /// Fill `dst` in one shot: read its raw bytes directly, then fix up
+    /// the byte order of each element. Much faster than calling `read_as`
+    /// once per element for large slices (audio samples, vertex buffers).
+    fn read_into<B: ByteTransform<T>, T: Copy>(&mut self, dst: &mut [T]) -> io::Result<()>;
+
+    /// Read a fixed `nbytes`-wide (`1..=8`) unsigned integer into a
+    /// `usize`, erroring instead of wrapping if it doesn't fit. Prefer
+    /// this over a native `usize`/`isize` width, which isn't reproducible
+    /// across 32-bit and 64-bit targets.
+    fn read_as_usize<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<usize>;
+
+    /// Read a fixed `nbytes`-wide (`1..=8`) signed integer into an
+    /// `isize`, erroring instead of wrapping if it doesn't fit.
+    fn read_as_isize<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<isize>;
 }
 
 /// Extension trait that allows to write endian specified primitive types to it
 pub trait EndianWriteExt {
     fn write_as<B: ByteTransform<T>, T>(&mut self, val: T) -> io::Result<()>;
+
+    /// Write the low `nbytes` (`1..=8`) bytes of `val`.
+    fn write_uint<B: VariableWidth>(&mut self, val: u64, nbytes: usize) -> io::Result<()>;
+
+    /// Write the low `nbytes` (`1..=8`) bytes of `val`.
+    fn write_int<B: VariableWidth>(&mut self, val: i64, nbytes: usize) -> io::Result<()>;
+
+    /// Write all of `src` in one shot: byte-swap a temporary copy (when
+    /// needed) and write its raw bytes directly, instead of calling
+    /// `write_as` once per element. When `B` is `NativeByteOrder`, skips
+    /// the swap entirely and writes `src`'s bytes with no allocation.
+    fn write_slice<B: ByteTransform<T> + 'static, T: Copy>(&mut self, src: &[T]) -> io::Result<()>;
+
+    /// Write `val` as a fixed `nbytes`-wide (`1..=8`) unsigned integer.
+    fn write_as_usize<B: VariableWidth>(&mut self, val: usize, nbytes: usize) -> io::Result<()>;
+
+    /// Write `val` as a fixed `nbytes`-wide (`1..=8`) signed integer.
+    fn write_as_isize<B: VariableWidth>(&mut self, val: isize, nbytes: usize) -> io::Result<()>;
 }
 
 impl<R: io::Read> EndianReadExt for R {
@@ -148,6 +327,58 @@ impl<R: io::Read> EndianReadExt for R {
         }
         Ok(B::from_bytes(buf))
     }
+
+    fn read_uint<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<u64> {
+        try!(check_nbytes(nbytes));
+        let mut buf = [0u8; 8];
+        let read_len = try!(self.read(&mut buf[..nbytes]));
+        if read_len != nbytes {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "could not read all bytes"
+            ))
+        }
+        Ok(B::assemble(&buf[..nbytes]))
+    }
+
+    fn read_int<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<i64> {
+        let val = try!(self.read_uint::<B>(nbytes));
+        Ok(extend_sign(val, nbytes))
+    }
+
+    fn read_into<B: ByteTransform<T>, T: Copy>(&mut self, dst: &mut [T]) -> io::Result<()> {
+        let byte_len = dst.len() * size_of::<T>();
+        let bytes = unsafe {
+            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u8, byte_len)
+        };
+        let read_len = try!(self.read(bytes));
+        if read_len != byte_len {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "could not read all bytes"
+            ))
+        }
+        for elem in dst.iter_mut() {
+            *elem = B::correct(*elem);
+        }
+        Ok(())
+    }
+
+    fn read_as_usize<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<usize> {
+        let val = try!(self.read_uint::<B>(nbytes));
+        usize::try_from(val).map_err(|_| io::Error::new(
+            io::ErrorKind::InvalidData,
+            "value does not fit in usize"
+        ))
+    }
+
+    fn read_as_isize<B: VariableWidth>(&mut self, nbytes: usize) -> io::Result<isize> {
+        let val = try!(self.read_int::<B>(nbytes));
+        isize::try_from(val).map_err(|_| io::Error::new(
+            io::ErrorKind::InvalidData,
+            "value does not fit in isize"
+        ))
+    }
 }
 
 impl<W: io::Write> EndianWriteExt for W {
@@ -156,11 +387,51 @@ impl<W: io::Write> EndianWriteExt for W {
         let buf = B::to_bytes(val);
         self.write_all(buf.as_ref())
     }
+
+    fn write_uint<B: VariableWidth>(&mut self, val: u64, nbytes: usize) -> io::Result<()> {
+        try!(check_nbytes(nbytes));
+        let mut buf = [0u8; 8];
+        B::disassemble(val, &mut buf[..nbytes]);
+        self.write_all(&buf[..nbytes])
+    }
+
+    fn write_int<B: VariableWidth>(&mut self, val: i64, nbytes: usize) -> io::Result<()> {
+        self.write_uint::<B>(val as u64, nbytes)
+    }
+
+    fn write_slice<B: ByteTransform<T> + 'static, T: Copy>(&mut self, src: &[T]) -> io::Result<()> {
+        if TypeId::of::<B>() == TypeId::of::<NativeByteOrder>() {
+            // Already in native order: write `src`'s bytes as-is, no
+            // allocation and no per-element swap loop.
+            let bytes = unsafe {
+                slice::from_raw_parts(src.as_ptr() as *const u8, src.len() * size_of::<T>())
+            };
+            return self.write_all(bytes);
+        }
+
+        let mut corrected: Vec<T> = src.to_vec();
+        for elem in corrected.iter_mut() {
+            *elem = B::correct(*elem);
+        }
+        let bytes = unsafe {
+            slice::from_raw_parts(corrected.as_ptr() as *const u8, corrected.len() * size_of::<T>())
+        };
+        self.write_all(bytes)
+    }
+
+    fn write_as_usize<B: VariableWidth>(&mut self, val: usize, nbytes: usize) -> io::Result<()> {
+        self.write_uint::<B>(val as u64, nbytes)
+    }
+
+    fn write_as_isize<B: VariableWidth>(&mut self, val: isize, nbytes: usize) -> io::Result<()> {
+        self.write_int::<B>(val as i64, nbytes)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{BigEndian, LittleEndian, EndianReadExt, EndianWriteExt};
+    use std::mem::size_of;
+    use super::{BigEndian, LittleEndian, NativeByteOrder, EndianReadExt, EndianWriteExt};
 
     #[test]
     fn test_all() {
@@ -207,4 +478,133 @@ mod test {
         run_test!(f32, 4, 1.2795344e-28, 7.165323e2);
         run_test!(f64, 8, 3.841412024471731e-226, -7.086876636573014e-268);
     }
+
+    #[test]
+    fn test_128bit() {
+        let expected: [u8; 16] = [
+            0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88,
+            0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff, 0x00,
+        ];
+
+        let big: u128 = 0x112233445566778899aabbccddeeff00;
+        let little: u128 = 0x00ffeeddccbbaa998877665544332211;
+
+        assert_eq!(big, (&expected[..]).read_as::<BigEndian, u128>().unwrap());
+        assert_eq!(little, (&expected[..]).read_as::<LittleEndian, u128>().unwrap());
+
+        let mut buf: Vec<u8> = vec![0; 16];
+        (&mut buf[..]).write_as::<BigEndian, u128>(big).unwrap();
+        assert_eq!(&expected[..], &buf[..]);
+
+        (&mut buf[..]).write_as::<LittleEndian, u128>(little).unwrap();
+        assert_eq!(&expected[..], &buf[..]);
+    }
+
+    #[test]
+    fn test_fixed_width_size() {
+        let data: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+
+        assert_eq!(65536usize, (&data[..]).read_as_usize::<BigEndian>(4).unwrap());
+        assert_eq!(65536isize, (&data[..]).read_as_isize::<BigEndian>(4).unwrap());
+
+        let mut buf: Vec<u8> = vec![0; 4];
+        (&mut buf[..]).write_as_usize::<BigEndian>(65536, 4).unwrap();
+        assert_eq!(&data[..], &buf[..]);
+
+        (&mut buf[..]).write_as_isize::<BigEndian>(65536, 4).unwrap();
+        assert_eq!(&data[..], &buf[..]);
+
+        // Doesn't fit in a 32-bit usize
+        if size_of::<usize>() < 8 {
+            let wide: [u8; 8] = [0xff; 8];
+            (&wide[..]).read_as_usize::<BigEndian>(8).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_slice_transform() {
+        let data: [u8; 4] = [0x01, 0x23, 0x45, 0x67];
+
+        assert_eq!(0x01234567, BigEndian::read::<u32>(&data));
+        assert_eq!(0x67452301, LittleEndian::read::<u32>(&data));
+
+        let mut buf: [u8; 4] = [0; 4];
+        BigEndian::write(&mut buf, 0x01234567u32);
+        assert_eq!(&data[..], &buf[..]);
+
+        LittleEndian::write(&mut buf, 0x01234567u32);
+        assert_eq!(&[0x67, 0x45, 0x23, 0x01], &buf[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_slice_transform_short_buffer() {
+        BigEndian::read::<u32>(&[0x01, 0x02]);
+    }
+
+    #[test]
+    fn test_variable_width() {
+        let data = [0x11, 0x22, 0x33, 0x44, 0x55];
+
+        assert_eq!(0x1122334455, (&data[..]).read_uint::<BigEndian>(5).unwrap());
+        assert_eq!(0x5544332211, (&data[..]).read_uint::<LittleEndian>(5).unwrap());
+
+        let neg: [u8; 3] = [0xff, 0x00, 0x00];
+        assert_eq!(-65536, (&neg[..]).read_int::<BigEndian>(3).unwrap());
+        assert_eq!(255, (&neg[..]).read_int::<LittleEndian>(3).unwrap());
+
+        let mut buf: Vec<u8> = vec![0; 5];
+        (&mut buf[..]).write_uint::<BigEndian>(0x1122334455, 5).unwrap();
+        assert_eq!(&data[..], &buf[..]);
+
+        (&mut buf[..]).write_uint::<LittleEndian>(0x1122334455, 5).unwrap();
+        assert_eq!(&[0x55, 0x44, 0x33, 0x22, 0x11], &buf[..]);
+
+        (&[0u8; 5][..]).read_uint::<BigEndian>(0).unwrap_err();
+        (&[0u8; 5][..]).read_uint::<BigEndian>(9).unwrap_err();
+        (&[0u8; 4][..]).read_uint::<BigEndian>(5).unwrap_err();
+    }
+
+    #[test]
+    fn test_bulk_slice() {
+        let data: [u8; 8] = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+
+        let mut dst: [u16; 4] = [0; 4];
+        (&data[..]).read_into::<BigEndian, u16>(&mut dst).unwrap();
+        assert_eq!([0x1122, 0x3344, 0x5566, 0x7788], dst);
+
+        let mut dst: [u16; 4] = [0; 4];
+        (&data[..]).read_into::<LittleEndian, u16>(&mut dst).unwrap();
+        assert_eq!([0x2211, 0x4433, 0x6655, 0x8877], dst);
+
+        // Trailing partial element
+        (&data[0..7]).read_into::<BigEndian, u16>(&mut [0u16; 4]).unwrap_err();
+
+        let src: [u16; 4] = [0x1122, 0x3344, 0x5566, 0x7788];
+        let mut buf: Vec<u8> = vec![0; 8];
+        (&mut buf[..]).write_slice::<BigEndian, u16>(&src).unwrap();
+        assert_eq!(&data[..], &buf[..]);
+        // src must be untouched
+        assert_eq!([0x1122, 0x3344, 0x5566, 0x7788], src);
+
+        let mut buf: Vec<u8> = vec![0; 8];
+        (&mut buf[..]).write_slice::<LittleEndian, u16>(&src).unwrap();
+        assert_eq!(&[0x22, 0x11, 0x44, 0x33, 0x66, 0x55, 0x88, 0x77], &buf[..]);
+    }
+
+    #[test]
+    fn test_write_slice_native_order_skips_swap() {
+        // When `B` is `NativeByteOrder`, `write_slice` must write `src`'s
+        // bytes as-is rather than going through `B::correct`.
+        let src: [u16; 4] = [0x1122, 0x3344, 0x5566, 0x7788];
+        let mut buf: Vec<u8> = vec![0; 8];
+        (&mut buf[..]).write_slice::<NativeByteOrder, u16>(&src).unwrap();
+
+        let mut expect: Vec<u8> = vec![0; 8];
+        (&mut expect[..]).write_as::<NativeByteOrder, u16>(src[0]).unwrap();
+        (&mut expect[2..]).write_as::<NativeByteOrder, u16>(src[1]).unwrap();
+        (&mut expect[4..]).write_as::<NativeByteOrder, u16>(src[2]).unwrap();
+        (&mut expect[6..]).write_as::<NativeByteOrder, u16>(src[3]).unwrap();
+        assert_eq!(expect, buf);
+    }
 }
@@ -0,0 +1,100 @@
+//! LEB128 variable-length integer codec, independent of the fixed-width
+//! `ByteTransform` path: each byte carries 7 payload bits, with the high
+//! bit signaling that more bytes follow. Gives compact encoding for small
+//! values without committing to a byte count up front.
+
+use std::io;
+
+/// Read an unsigned LEB128-encoded `u64`.
+pub fn read_varint<R: io::Read>(r: &mut R) -> io::Result<u64> {
+    let mut result: u64 = 0;
+    let mut shift: u32 = 0;
+    loop {
+        if shift > 63 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "malformed varint: too many bytes"
+            ))
+        }
+
+        let mut byte = [0u8; 1];
+        let read_len = try!(r.read(&mut byte));
+        if read_len != 1 {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "could not read all bytes"
+            ))
+        }
+
+        result |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(result)
+        }
+        shift += 7;
+    }
+}
+
+/// Write `val` as an unsigned LEB128 varint.
+pub fn write_varint<W: io::Write>(w: &mut W, val: u64) -> io::Result<()> {
+    let mut val = val;
+    loop {
+        let mut byte = (val & 0x7f) as u8;
+        val >>= 7;
+        if val != 0 {
+            byte |= 0x80;
+        }
+        try!(w.write_all(&[byte]));
+        if val == 0 {
+            return Ok(())
+        }
+    }
+}
+
+/// Read a zigzag+LEB128-encoded signed `i64`.
+pub fn read_varint_signed<R: io::Read>(r: &mut R) -> io::Result<i64> {
+    let u = try!(read_varint(r));
+    Ok(((u >> 1) as i64) ^ -((u & 1) as i64))
+}
+
+/// Write `val` as a zigzag+LEB128 varint.
+pub fn write_varint_signed<W: io::Write>(w: &mut W, val: i64) -> io::Result<()> {
+    let zigzagged = ((val << 1) ^ (val >> 63)) as u64;
+    write_varint(w, zigzagged)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_varint, write_varint, read_varint_signed, write_varint_signed};
+
+    #[test]
+    fn test_varint() {
+        let cases: [(u64, &[u8]); 4] = [
+            (0, &[0x00]),
+            (1, &[0x01]),
+            (127, &[0x7f]),
+            (300, &[0xac, 0x02]),
+        ];
+
+        for &(val, encoded) in cases.iter() {
+            assert_eq!(val, read_varint(&mut &encoded[..]).unwrap());
+
+            let mut buf: Vec<u8> = Vec::new();
+            write_varint(&mut buf, val).unwrap();
+            assert_eq!(encoded, &buf[..]);
+        }
+
+        // Truncated input
+        read_varint(&mut &[0xac][..]).unwrap_err();
+        // Malformed: never terminates within 64 bits
+        read_varint(&mut &[0xff; 10][..]).unwrap_err();
+    }
+
+    #[test]
+    fn test_varint_signed() {
+        for &val in [0i64, 1, -1, 2, -2, 300, -300, i64::MIN, i64::MAX].iter() {
+            let mut buf: Vec<u8> = Vec::new();
+            write_varint_signed(&mut buf, val).unwrap();
+            assert_eq!(val, read_varint_signed(&mut &buf[..]).unwrap());
+        }
+    }
+}
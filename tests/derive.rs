@@ -0,0 +1,62 @@
+#![cfg(feature = "derive")]
+
+extern crate endianrw;
+
+use endianrw::BigEndian;
+use endianrw::codec::{ReadFrom, WriteTo};
+use endianrw::EndianIO;
+
+#[derive(EndianIO, Debug, PartialEq)]
+struct Header {
+    magic: u32,
+    #[endian(LittleEndian)]
+    version: u16,
+    flags: u8,
+}
+
+#[test]
+fn round_trips_default_and_overridden_field_order() {
+    let header = Header {
+        magic: 0x89504e47,
+        version: 0x0102,
+        flags: 0x07,
+    };
+
+    let mut buf: Vec<u8> = Vec::new();
+    <Header as WriteTo<BigEndian>>::write_to(&header, &mut buf).unwrap();
+
+    // magic (big endian) + version (little endian override) + flags
+    assert_eq!(&[0x89, 0x50, 0x4e, 0x47, 0x02, 0x01, 0x07], &buf[..]);
+
+    let decoded = <Header as ReadFrom<BigEndian>>::read_from(&mut &buf[..]).unwrap();
+    assert_eq!(header, decoded);
+}
+
+#[derive(EndianIO, Debug, PartialEq)]
+struct Point {
+    x: u16,
+    y: u16,
+}
+
+#[derive(EndianIO, Debug, PartialEq)]
+struct Rect {
+    #[endian(nested)]
+    top_left: Point,
+    #[endian(nested)]
+    bottom_right: Point,
+}
+
+#[test]
+fn round_trips_nested_fields() {
+    let rect = Rect {
+        top_left: Point { x: 0x0001, y: 0x0002 },
+        bottom_right: Point { x: 0x0003, y: 0x0004 },
+    };
+
+    let mut buf: Vec<u8> = Vec::new();
+    <Rect as WriteTo<BigEndian>>::write_to(&rect, &mut buf).unwrap();
+    assert_eq!(&[0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04], &buf[..]);
+
+    let decoded = <Rect as ReadFrom<BigEndian>>::read_from(&mut &buf[..]).unwrap();
+    assert_eq!(rect, decoded);
+}